@@ -0,0 +1,135 @@
+//! Multi-mode HTTP body decoder, mirroring hyper's `Decoder { kind }`.
+//!
+//! An HTTP message body can be framed in one of three ways: a fixed
+//! `Content-Length`, `Transfer-Encoding: chunked`, or no framing at all
+//! (the body runs until the connection closes). [`BodyDecoder`] wraps
+//! [`ChunkedDecoder`] for the chunked case and unifies `is_done`/`finalize`
+//! semantics across all three.
+
+use crate::decoder::{ChunkedDecoder, DecodeError};
+
+/// A body's framing mode.
+#[derive(Debug)]
+pub enum BodyDecoder {
+    /// Exactly `n` more payload bytes remain, as given by `Content-Length`.
+    Length(u64),
+    /// `Transfer-Encoding: chunked` framing.
+    Chunked(ChunkedDecoder),
+    /// No framing: forward everything; done only once the caller signals
+    /// end-of-stream via [`BodyDecoder::decode_eof`].
+    Eof(bool),
+}
+
+impl BodyDecoder {
+    pub fn length(len: u64) -> Self {
+        BodyDecoder::Length(len)
+    }
+
+    pub fn chunked() -> Self {
+        BodyDecoder::Chunked(ChunkedDecoder::new())
+    }
+
+    pub fn eof() -> Self {
+        BodyDecoder::Eof(false)
+    }
+
+    /// Decodes a fragment of body bytes, delivering payload to `on_data`.
+    /// A no-op once the decoder is done.
+    pub fn decode<F>(&mut self, data: &[u8], mut on_data: F) -> Result<(), DecodeError>
+    where
+        F: FnMut(&[u8]),
+    {
+        match self {
+            BodyDecoder::Length(remaining) => {
+                let take = (*remaining).min(data.len() as u64) as usize;
+                if take > 0 {
+                    on_data(&data[..take]);
+                    *remaining -= take as u64;
+                }
+                Ok(())
+            }
+            BodyDecoder::Chunked(decoder) => decoder.decode_chunk_bytes(data, on_data),
+            BodyDecoder::Eof(done) => {
+                if !*done && !data.is_empty() {
+                    on_data(data);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Signals end-of-stream. Only meaningful for [`BodyDecoder::Eof`],
+    /// which has no other way to detect the end of the body; a no-op for
+    /// `Length` and `Chunked`, which detect their own end from the stream.
+    pub fn decode_eof(&mut self) {
+        if let BodyDecoder::Eof(done) = self {
+            *done = true;
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        match self {
+            BodyDecoder::Length(remaining) => *remaining == 0,
+            BodyDecoder::Chunked(decoder) => decoder.is_done(),
+            BodyDecoder::Eof(done) => *done,
+        }
+    }
+
+    pub fn finalize(&self) -> Result<(), DecodeError> {
+        if self.is_done() {
+            Ok(())
+        } else {
+            Err(DecodeError::NotFinished)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_mode_forwards_exactly_n_bytes_then_is_done() {
+        let mut out = Vec::new();
+        let mut body = BodyDecoder::length(5);
+
+        body.decode(b"hel", |d| out.extend_from_slice(d)).unwrap();
+        assert!(!body.is_done());
+
+        body.decode(b"lo world", |d| out.extend_from_slice(d)).unwrap();
+        assert!(body.is_done());
+        body.finalize().unwrap();
+
+        assert_eq!(out, b"hello");
+    }
+
+    #[test]
+    fn chunked_mode_delegates_to_chunked_decoder() {
+        let mut out = Vec::new();
+        let mut body = BodyDecoder::chunked();
+
+        body.decode(b"5\r\nhello\r\n0\r\n\r\n", |d| out.extend_from_slice(d))
+            .unwrap();
+        assert!(body.is_done());
+        body.finalize().unwrap();
+
+        assert_eq!(out, b"hello");
+    }
+
+    #[test]
+    fn eof_mode_is_only_done_after_decode_eof() {
+        let mut out = Vec::new();
+        let mut body = BodyDecoder::eof();
+
+        body.decode(b"hello ", |d| out.extend_from_slice(d)).unwrap();
+        body.decode(b"world", |d| out.extend_from_slice(d)).unwrap();
+        assert!(!body.is_done());
+        assert_eq!(body.finalize(), Err(DecodeError::NotFinished));
+
+        body.decode_eof();
+        assert!(body.is_done());
+        body.finalize().unwrap();
+
+        assert_eq!(out, b"hello world");
+    }
+}
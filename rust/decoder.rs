@@ -4,9 +4,16 @@
 //!   `<hex-size>\r\n<payload>\r\n ... 0\r\n\r\n`
 //!
 //! Matches the TypeScript version (`src/decoder.ts`):
-//! - Input is provided as text fragments (`&str`), assumed to be ASCII.
-//! - Chunk size counts characters (ASCII => 1 char == 1 byte).
-//! - No chunk extensions and no trailers.
+//! - `decode_chunk` takes `&str` fragments and requires ASCII input;
+//!   `decode_chunk_bytes` takes raw `&[u8]` and places no such requirement
+//!   on the payload (only the framing around it is ASCII).
+//! - Chunk size counts bytes and is accumulated as `u64` for consistent
+//!   framing semantics across 32- and 64-bit targets; an optional
+//!   `with_max_chunk_size` guard rejects hostile size lines early.
+//! - Chunk extensions (`;name=value`) are recognized and discarded by
+//!   default.
+//! - Trailer headers after the terminating zero-size chunk are parsed and
+//!   discarded by default; obs-fold continuation lines are not supported.
 
 use std::error::Error;
 use std::fmt;
@@ -18,7 +25,10 @@ pub enum DecodeError {
     EmptyChunkSize,
     InvalidChunkSize,
     ChunkSizeOverflow,
+    ChunkSizeTooLarge,
     ExpectedCrlf,
+    ExpectedLfAfterCrInTrailerLine,
+    InvalidTrailer,
     NotFinished,
 }
 
@@ -36,7 +46,17 @@ impl fmt::Display for DecodeError {
             DecodeError::EmptyChunkSize => write!(f, "Invalid chunk size: \"\""),
             DecodeError::InvalidChunkSize => write!(f, "Invalid chunk size."),
             DecodeError::ChunkSizeOverflow => write!(f, "Invalid chunk size (overflow)."),
+            DecodeError::ChunkSizeTooLarge => {
+                write!(f, "Chunk size exceeds the configured maximum.")
+            }
             DecodeError::ExpectedCrlf => write!(f, "Invalid chunked encoding: expected CRLF."),
+            DecodeError::ExpectedLfAfterCrInTrailerLine => write!(
+                f,
+                "Invalid chunked encoding: expected LF after CR in trailer line."
+            ),
+            DecodeError::InvalidTrailer => {
+                write!(f, "Invalid trailer header: expected \"Name: value\".")
+            }
             DecodeError::NotFinished => write!(f, "Chunked stream not finished."),
         }
     }
@@ -47,16 +67,15 @@ impl Error for DecodeError {}
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum State {
     Size,
+    Extension,
     Payload,
     ExpectCrlf,
+    Trailers,
     Done,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum AfterExpect {
-    Size,
-    Done,
-}
+type ExtensionCallback = Box<dyn FnMut(&str)>;
+type TrailerCallback = Box<dyn FnMut(&str, &str)>;
 
 fn hex_value(b: u8) -> Option<u8> {
     match b {
@@ -68,21 +87,44 @@ fn hex_value(b: u8) -> Option<u8> {
 }
 
 /// Streaming decoder state machine (greedy, minimal buffering).
-#[derive(Debug)]
 pub struct ChunkedDecoder {
     state: State,
 
     // SIZE parsing
-    size_acc: usize,
+    size_acc: u64,
     size_digits: usize,
+    max_chunk_size: Option<u64>,
     saw_cr: bool,
 
+    // EXTENSION parsing (the `;name=value` tail of a size line)
+    ext_buf: String,
+    on_extension: Option<ExtensionCallback>,
+
     // PAYLOAD parsing
-    remaining: usize,
+    remaining: u64,
 
     // CRLF expectation parsing
     expect_index: u8, // 0 => expect '\r', 1 => expect '\n'
-    after_expect: AfterExpect,
+
+    // TRAILERS parsing (after the terminating zero-size chunk)
+    trailer_buf: String,
+    on_trailer: Option<TrailerCallback>,
+}
+
+impl fmt::Debug for ChunkedDecoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkedDecoder")
+            .field("state", &self.state)
+            .field("size_acc", &self.size_acc)
+            .field("size_digits", &self.size_digits)
+            .field("max_chunk_size", &self.max_chunk_size)
+            .field("saw_cr", &self.saw_cr)
+            .field("ext_buf", &self.ext_buf)
+            .field("remaining", &self.remaining)
+            .field("expect_index", &self.expect_index)
+            .field("trailer_buf", &self.trailer_buf)
+            .finish()
+    }
 }
 
 impl ChunkedDecoder {
@@ -91,13 +133,51 @@ impl ChunkedDecoder {
             state: State::Size,
             size_acc: 0,
             size_digits: 0,
+            max_chunk_size: None,
             saw_cr: false,
+            ext_buf: String::new(),
+            on_extension: None,
             remaining: 0,
             expect_index: 0,
-            after_expect: AfterExpect::Size,
+            trailer_buf: String::new(),
+            on_trailer: None,
         }
     }
 
+    /// Registers a callback invoked with each chunk extension's raw text
+    /// (e.g. `"foo=bar"`, or `"token"` for a valueless extension) as it is
+    /// parsed. By default extensions are discarded.
+    pub fn with_on_extension<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str) + 'static,
+    {
+        self.on_extension = Some(Box::new(callback));
+        self
+    }
+
+    /// Registers a callback invoked with each trailer header's `(name,
+    /// value)` as it is parsed, after the terminating zero-size chunk. By
+    /// default trailers are discarded.
+    pub fn with_on_trailer<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&str, &str) + 'static,
+    {
+        self.on_trailer = Some(Box::new(callback));
+        self
+    }
+
+    /// Rejects any chunk whose size line exceeds `max` bytes, as soon as
+    /// the size digits accumulate past it and before any payload is
+    /// consumed. Defends streaming consumers against hostile size lines
+    /// that would otherwise demand unbounded buffering.
+    pub fn with_max_chunk_size(mut self, max: u64) -> Self {
+        self.max_chunk_size = Some(max);
+        self
+    }
+
+    /// Decodes a fragment of ASCII text, delivering decoded payload to
+    /// `on_data` as `&str`. Delegates to [`ChunkedDecoder::decode_chunk_bytes`];
+    /// use that method directly for binary (non-ASCII) payloads.
     pub fn decode_chunk<F>(&mut self, chunk: &str, mut on_data: F) -> Result<(), DecodeError>
     where
         F: FnMut(&str),
@@ -110,7 +190,24 @@ impl ChunkedDecoder {
             return Err(DecodeError::NonAsciiInput);
         }
 
-        let bytes = chunk.as_bytes();
+        self.decode_chunk_bytes(chunk.as_bytes(), |data| {
+            // ASCII-checked above, so every byte slice is valid UTF-8.
+            on_data(std::str::from_utf8(data).unwrap())
+        })
+    }
+
+    /// Decodes a fragment of raw bytes, delivering decoded payload to
+    /// `on_data` as `&[u8]`. Unlike [`ChunkedDecoder::decode_chunk`], the
+    /// payload is not required to be ASCII; only the framing (size lines,
+    /// extensions, trailers) is.
+    pub fn decode_chunk_bytes<F>(&mut self, bytes: &[u8], mut on_data: F) -> Result<(), DecodeError>
+    where
+        F: FnMut(&[u8]),
+    {
+        if self.state == State::Done {
+            return Ok(());
+        }
+
         let mut i = 0usize;
         while i < bytes.len() {
             match self.state {
@@ -134,7 +231,7 @@ impl ChunkedDecoder {
                         self.remaining = n;
 
                         if n == 0 {
-                            self.start_expect_crlf(AfterExpect::Done);
+                            self.state = State::Trailers;
                         } else {
                             self.state = State::Payload;
                         }
@@ -146,6 +243,17 @@ impl ChunkedDecoder {
                         continue;
                     }
 
+                    if b == b';' {
+                        // A chunk extension is only honored once at least one
+                        // size digit has accumulated; otherwise this is a
+                        // malformed size line and we defer to the usual
+                        // `EmptyChunkSize` check at the terminating LF.
+                        if self.size_digits > 0 {
+                            self.state = State::Extension;
+                        }
+                        continue;
+                    }
+
                     let Some(d) = hex_value(b) else {
                         return Err(DecodeError::InvalidChunkSize);
                     };
@@ -153,23 +261,63 @@ impl ChunkedDecoder {
                     self.size_acc = self
                         .size_acc
                         .checked_mul(16)
-                        .and_then(|v| v.checked_add(d as usize))
+                        .and_then(|v| v.checked_add(d as u64))
                         .ok_or(DecodeError::ChunkSizeOverflow)?;
                     self.size_digits += 1;
+
+                    if let Some(max) = self.max_chunk_size {
+                        if self.size_acc > max {
+                            return Err(DecodeError::ChunkSizeTooLarge);
+                        }
+                    }
+                }
+                State::Extension => {
+                    let b = bytes[i];
+                    i += 1;
+
+                    if self.saw_cr {
+                        if b != b'\n' {
+                            return Err(DecodeError::ExpectedLfAfterCrInSizeLine);
+                        }
+                        self.saw_cr = false;
+
+                        if let Some(cb) = self.on_extension.as_mut() {
+                            cb(&self.ext_buf);
+                        }
+                        self.ext_buf.clear();
+
+                        let n = self.size_acc;
+                        self.size_acc = 0;
+                        self.size_digits = 0;
+                        self.remaining = n;
+
+                        if n == 0 {
+                            self.state = State::Trailers;
+                        } else {
+                            self.state = State::Payload;
+                        }
+                        continue;
+                    }
+
+                    if b == b'\r' {
+                        self.saw_cr = true;
+                        continue;
+                    }
+
+                    self.ext_buf.push(b as char);
                 }
                 State::Payload => {
-                    let available = bytes.len() - i;
-                    let take = self.remaining.min(available);
+                    let available = (bytes.len() - i) as u64;
+                    let take = self.remaining.min(available) as usize;
 
                     if take > 0 {
-                        // ASCII => byte indices are valid UTF-8 boundaries.
-                        on_data(&chunk[i..i + take]);
+                        on_data(&bytes[i..i + take]);
                         i += take;
-                        self.remaining -= take;
+                        self.remaining -= take as u64;
                     }
 
                     if self.remaining == 0 {
-                        self.start_expect_crlf(AfterExpect::Size);
+                        self.start_expect_crlf();
                     }
                 }
                 State::ExpectCrlf => {
@@ -184,14 +332,40 @@ impl ChunkedDecoder {
                     self.expect_index += 1;
                     if self.expect_index == 2 {
                         self.expect_index = 0;
-                        self.state = match self.after_expect {
-                            AfterExpect::Done => State::Done,
-                            AfterExpect::Size => State::Size,
-                        };
-                        if self.state == State::Done {
+                        self.state = State::Size;
+                    }
+                }
+                State::Trailers => {
+                    let b = bytes[i];
+                    i += 1;
+
+                    if self.saw_cr {
+                        if b != b'\n' {
+                            return Err(DecodeError::ExpectedLfAfterCrInTrailerLine);
+                        }
+                        self.saw_cr = false;
+
+                        if self.trailer_buf.is_empty() {
+                            self.state = State::Done;
                             return Ok(());
                         }
+
+                        let line = std::mem::take(&mut self.trailer_buf);
+                        let colon = line.find(':').ok_or(DecodeError::InvalidTrailer)?;
+                        let name = line[..colon].trim();
+                        let value = line[colon + 1..].trim();
+                        if let Some(cb) = self.on_trailer.as_mut() {
+                            cb(name, value);
+                        }
+                        continue;
                     }
+
+                    if b == b'\r' {
+                        self.saw_cr = true;
+                        continue;
+                    }
+
+                    self.trailer_buf.push(b as char);
                 }
                 State::Done => return Ok(()),
             }
@@ -212,10 +386,9 @@ impl ChunkedDecoder {
         }
     }
 
-    fn start_expect_crlf(&mut self, next: AfterExpect) {
+    fn start_expect_crlf(&mut self) {
         self.state = State::ExpectCrlf;
         self.expect_index = 0;
-        self.after_expect = next;
     }
 }
 
@@ -229,7 +402,7 @@ impl Default for ChunkedDecoder {
 #[derive(Debug, Default)]
 pub struct ChunkedCollectingDecoder {
     decoder: ChunkedDecoder,
-    result: String,
+    result: Vec<u8>,
 }
 
 impl ChunkedCollectingDecoder {
@@ -239,7 +412,14 @@ impl ChunkedCollectingDecoder {
 
     pub fn decode_chunk(&mut self, chunk: &str) -> Result<(), DecodeError> {
         let out = &mut self.result;
-        self.decoder.decode_chunk(chunk, |frag| out.push_str(frag))
+        self.decoder
+            .decode_chunk(chunk, |frag| out.extend_from_slice(frag.as_bytes()))
+    }
+
+    pub fn decode_chunk_bytes(&mut self, chunk: &[u8]) -> Result<(), DecodeError> {
+        let out = &mut self.result;
+        self.decoder
+            .decode_chunk_bytes(chunk, |frag| out.extend_from_slice(frag))
     }
 
     pub fn is_done(&self) -> bool {
@@ -250,9 +430,15 @@ impl ChunkedCollectingDecoder {
         self.decoder.finalize()
     }
 
-    pub fn result(&self) -> &str {
+    pub fn result(&self) -> &[u8] {
         &self.result
     }
+
+    /// Validates the accumulated payload as UTF-8. Only performed on
+    /// demand, so binary payloads never pay for a UTF-8 scan.
+    pub fn as_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.result)
+    }
 }
 
 /// Backwards-compatible alias.
@@ -321,7 +507,128 @@ mod tests {
             dec.decode_chunk(std::str::from_utf8(frag).unwrap()).unwrap();
         }
         dec.finalize().unwrap();
-        assert_eq!(dec.result(), payload);
+        assert_eq!(dec.as_str().unwrap(), payload);
+    }
+
+    #[test]
+    fn chunk_extensions_are_discarded_by_default() {
+        let encoded = "4;foo=bar\r\nWiki\r\n5;baz\r\npedia\r\n0\r\n\r\n";
+
+        let mut out = String::new();
+        let mut decoder = ChunkedDecoder::new();
+        decoder.decode_chunk(encoded, |d| out.push_str(d)).unwrap();
+        decoder.finalize().unwrap();
+
+        assert_eq!(out, "Wikipedia");
+    }
+
+    #[test]
+    fn chunk_extensions_are_reported_via_callback() {
+        let encoded = "4;foo=bar\r\nWiki\r\n5;baz\r\npedia\r\n0\r\n\r\n";
+
+        let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let seen_cb = seen.clone();
+        let mut out = String::new();
+        let mut decoder = ChunkedDecoder::new().with_on_extension(move |ext| {
+            seen_cb.borrow_mut().push(ext.to_string());
+        });
+        decoder.decode_chunk(encoded, |d| out.push_str(d)).unwrap();
+        decoder.finalize().unwrap();
+
+        assert_eq!(out, "Wikipedia");
+        assert_eq!(*seen.borrow(), vec!["foo=bar", "baz"]);
+    }
+
+    #[test]
+    fn stray_semicolon_before_any_digit_defers_to_empty_chunk_size() {
+        let mut decoder = ChunkedDecoder::new();
+        let err = decoder.decode_chunk(";\r\n", |_| {}).unwrap_err();
+        assert_eq!(err, DecodeError::EmptyChunkSize);
+    }
+
+    #[test]
+    fn trailers_are_discarded_by_default() {
+        let encoded = "4\r\nWiki\r\n0\r\nChecksum: abc\r\nExpires: never\r\n\r\n";
+
+        let mut out = String::new();
+        let mut decoder = ChunkedDecoder::new();
+        decoder.decode_chunk(encoded, |d| out.push_str(d)).unwrap();
+        decoder.finalize().unwrap();
+
+        assert_eq!(out, "Wiki");
+    }
+
+    #[test]
+    fn trailers_are_reported_via_callback() {
+        let encoded = "4\r\nWiki\r\n0\r\nChecksum: abc\r\nExpires: never\r\n\r\n";
+
+        let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let seen_cb = seen.clone();
+        let mut out = String::new();
+        let mut decoder = ChunkedDecoder::new().with_on_trailer(move |name, value| {
+            seen_cb.borrow_mut().push((name.to_string(), value.to_string()));
+        });
+        decoder.decode_chunk(encoded, |d| out.push_str(d)).unwrap();
+        decoder.finalize().unwrap();
+
+        assert_eq!(out, "Wiki");
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                ("Checksum".to_string(), "abc".to_string()),
+                ("Expires".to_string(), "never".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_crlf_after_zero_chunk_skips_trailers() {
+        let encoded = "0\r\n\r\n";
+
+        let mut decoder = ChunkedDecoder::new();
+        decoder.decode_chunk(encoded, |_| {}).unwrap();
+        decoder.finalize().unwrap();
+    }
+
+    #[test]
+    fn decode_chunk_is_a_no_op_once_done_even_for_non_ascii_input() {
+        let mut decoder = ChunkedDecoder::new();
+        decoder.decode_chunk("0\r\n\r\n", |_| {}).unwrap();
+        assert!(decoder.is_done());
+
+        decoder.decode_chunk("\u{e9}xtra", |_| {}).unwrap();
+    }
+
+    #[test]
+    fn trailer_line_without_colon_is_invalid() {
+        let encoded = "0\r\nnotavalidheader\r\n\r\n";
+
+        let mut decoder = ChunkedDecoder::new();
+        let err = decoder.decode_chunk(encoded, |_| {}).unwrap_err();
+        assert_eq!(err, DecodeError::InvalidTrailer);
+    }
+
+    #[test]
+    fn max_chunk_size_guard_rejects_oversized_chunk() {
+        let mut decoder = ChunkedDecoder::new().with_max_chunk_size(0xff);
+        let err = decoder.decode_chunk("100\r\n", |_| {}).unwrap_err();
+        assert_eq!(err, DecodeError::ChunkSizeTooLarge);
+    }
+
+    #[test]
+    fn max_chunk_size_guard_allows_chunk_at_the_limit() {
+        let encoded = "ff\r\n";
+        let mut decoder = ChunkedDecoder::new().with_max_chunk_size(0xff);
+        decoder.decode_chunk(encoded, |_| {}).unwrap();
+    }
+
+    #[test]
+    fn chunk_size_beyond_u32_is_accepted() {
+        let size: u64 = (u32::MAX as u64) + 1;
+        let mut decoder = ChunkedDecoder::new();
+        decoder
+            .decode_chunk(&format!("{:x}\r\n", size), |_| {})
+            .unwrap();
     }
 
     #[test]
@@ -334,6 +641,40 @@ mod tests {
             dec.decode_chunk(std::str::from_utf8(frag).unwrap()).unwrap();
         }
         dec.finalize().unwrap();
+        assert_eq!(dec.as_str().unwrap(), payload);
+    }
+
+    #[test]
+    fn decode_chunk_bytes_handles_non_utf8_payload() {
+        let payload: &[u8] = &[0x57, 0x00, 0xff, 0xfe, 0x80];
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(format!("{:x}\r\n", payload.len()).as_bytes());
+        encoded.extend_from_slice(payload);
+        encoded.extend_from_slice(b"\r\n0\r\n\r\n");
+
+        let mut out = Vec::new();
+        let mut decoder = ChunkedDecoder::new();
+        decoder
+            .decode_chunk_bytes(&encoded, |d| out.extend_from_slice(d))
+            .unwrap();
+        decoder.finalize().unwrap();
+
+        assert_eq!(out, payload);
+    }
+
+    #[test]
+    fn collecting_decoder_as_str_rejects_non_utf8_payload() {
+        let payload: &[u8] = &[0xff, 0xfe];
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(format!("{:x}\r\n", payload.len()).as_bytes());
+        encoded.extend_from_slice(payload);
+        encoded.extend_from_slice(b"\r\n0\r\n\r\n");
+
+        let mut dec = ChunkedCollectingDecoder::new();
+        dec.decode_chunk_bytes(&encoded).unwrap();
+        dec.finalize().unwrap();
+
         assert_eq!(dec.result(), payload);
+        assert!(dec.as_str().is_err());
     }
 }
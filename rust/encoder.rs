@@ -0,0 +1,78 @@
+//! Streaming chunked *encoder*, the inverse of [`crate::decoder::ChunkedDecoder`].
+//!
+//! Turns arbitrary payload fragments into chunked-transfer-encoded framing,
+//! `<hex-size>\r\n<data>\r\n`, terminated by [`ChunkedEncoder::finish`]'s
+//! `0\r\n\r\n`. Generalizes the test-only `encode_chunked` helper in
+//! `decoder.rs` into a public, fragment-at-a-time API.
+
+use std::io::{self, Write};
+
+/// Stateless streaming chunked encoder. Each call is independent: unlike
+/// the decoder, encoding a chunk needs no buffering since its size is
+/// known up front.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ChunkedEncoder;
+
+impl ChunkedEncoder {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Writes `data` as one chunk: `<hex-len>\r\n<data>\r\n`. A no-op if
+    /// `data` is empty, since a zero-size chunk is the encoding's own
+    /// terminator (see [`ChunkedEncoder::finish`]).
+    pub fn encode_into<W: Write>(&self, data: &[u8], mut w: W) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        write!(w, "{:x}\r\n", data.len())?;
+        w.write_all(data)?;
+        w.write_all(b"\r\n")
+    }
+
+    /// Writes the terminating `0\r\n\r\n`. No trailers are supported.
+    pub fn finish<W: Write>(&self, mut w: W) -> io::Result<()> {
+        w.write_all(b"0\r\n\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::ChunkedDecoder;
+
+    #[test]
+    fn encodes_a_single_chunk() {
+        let mut out = Vec::new();
+        ChunkedEncoder::new().encode_into(b"hello", &mut out).unwrap();
+        assert_eq!(out, b"5\r\nhello\r\n");
+    }
+
+    #[test]
+    fn empty_data_encodes_to_nothing() {
+        let mut out = Vec::new();
+        ChunkedEncoder::new().encode_into(b"", &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_the_decoder() {
+        let fragments: &[&[u8]] = &[b"Hello, ", b"world", b"!"];
+
+        let encoder = ChunkedEncoder::new();
+        let mut encoded = Vec::new();
+        for frag in fragments {
+            encoder.encode_into(frag, &mut encoded).unwrap();
+        }
+        encoder.finish(&mut encoded).unwrap();
+
+        let mut out = Vec::new();
+        let mut decoder = ChunkedDecoder::new();
+        decoder
+            .decode_chunk_bytes(&encoded, |d| out.extend_from_slice(d))
+            .unwrap();
+        decoder.finalize().unwrap();
+
+        assert_eq!(out, b"Hello, world!");
+    }
+}
@@ -1,4 +1,7 @@
+mod body_decoder;
 mod decoder;
+mod encoder;
+mod reader;
 
 use std::io::{self, Read, Write};
 
@@ -23,7 +26,7 @@ fn main() {
         std::process::exit(1);
     }
 
-    if io::stdout().write_all(dec.result().as_bytes()).is_err() {
+    if io::stdout().write_all(dec.result()).is_err() {
         eprintln!("Failed to write stdout.");
         std::process::exit(2);
     }
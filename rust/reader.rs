@@ -0,0 +1,124 @@
+//! A pull-style `std::io::Read` adapter over [`crate::decoder::ChunkedDecoder`].
+//!
+//! Where `ChunkedDecoder`/`decode_chunk_bytes` are push-style (the caller
+//! hands in fragments and a callback), [`ChunkedReader`] wraps any `Read`
+//! (a socket, a file, ...) and transparently decodes chunked framing as the
+//! caller reads from it, without hand-managing fragments.
+
+use std::io::{self, Read};
+
+use crate::decoder::{ChunkedDecoder, DecodeError};
+
+/// Wraps a chunked-encoded `Read` and exposes the decoded payload through
+/// its own `Read` implementation.
+pub struct ChunkedReader<R> {
+    inner: R,
+    decoder: ChunkedDecoder,
+    // Decoded payload not yet handed to the caller.
+    out_buf: Vec<u8>,
+    out_pos: usize,
+}
+
+impl<R: Read> ChunkedReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            decoder: ChunkedDecoder::new(),
+            out_buf: Vec::new(),
+            out_pos: 0,
+        }
+    }
+
+    /// Unwraps this adapter, discarding any buffered but unread payload.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+fn to_io_error(e: DecodeError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+impl<R: Read> Read for ChunkedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.out_pos < self.out_buf.len() {
+                let n = (self.out_buf.len() - self.out_pos).min(buf.len());
+                buf[..n].copy_from_slice(&self.out_buf[self.out_pos..self.out_pos + n]);
+                self.out_pos += n;
+                if self.out_pos == self.out_buf.len() {
+                    self.out_buf.clear();
+                    self.out_pos = 0;
+                }
+                return Ok(n);
+            }
+
+            if self.decoder.is_done() {
+                return Ok(0);
+            }
+
+            let mut raw = [0u8; 4096];
+            let read = self.inner.read(&mut raw)?;
+            if read == 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    DecodeError::NotFinished,
+                ));
+            }
+
+            let out_buf = &mut self.out_buf;
+            self.decoder
+                .decode_chunk_bytes(&raw[..read], |d| out_buf.extend_from_slice(d))
+                .map_err(to_io_error)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_decoded_payload_transparently() {
+        let encoded = b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n".to_vec();
+        let mut reader = ChunkedReader::new(Cursor::new(encoded));
+
+        let mut out = String::new();
+        reader.read_to_string(&mut out).unwrap();
+
+        assert_eq!(out, "hello world");
+    }
+
+    #[test]
+    fn read_returns_zero_once_done() {
+        let encoded = b"1\r\nx\r\n0\r\n\r\n".to_vec();
+        let mut reader = ChunkedReader::new(Cursor::new(encoded));
+
+        let mut buf = [0u8; 16];
+        let n = reader.read(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"x");
+
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn read_surfaces_decode_errors_as_invalid_data() {
+        let encoded = b"zz\r\n".to_vec();
+        let mut reader = ChunkedReader::new(Cursor::new(encoded));
+
+        let mut buf = [0u8; 16];
+        let err = reader.read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn truncated_stream_surfaces_as_unexpected_eof() {
+        let encoded = b"5\r\nhel".to_vec();
+        let mut reader = ChunkedReader::new(Cursor::new(encoded));
+
+        let mut out = Vec::new();
+        let err = reader.read_to_end(&mut out).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}